@@ -0,0 +1,82 @@
+// Runtime-configurable backend endpoint and client profile, loaded from
+// `config.toml` in the Tauri app config dir. Lets the desktop app point at
+// staging/production without a rebuild.
+
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+const CONFIG_FILE_NAME: &str = "config.toml";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppConfig {
+    pub base_url: String,
+    pub client_type: String,
+    pub request_timeout_secs: u64,
+    pub verify_tls: bool,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            base_url: "http://127.0.0.1:8000".to_string(),
+            client_type: "desktop".to_string(),
+            request_timeout_secs: 30,
+            verify_tls: true,
+        }
+    }
+}
+
+/// Managed Tauri state wrapping the current config behind a mutex, since
+/// `set_base_url` can change it at runtime.
+pub struct ConfigState(pub Mutex<AppConfig>);
+
+fn config_path(app: &AppHandle) -> Result<std::path::PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join(CONFIG_FILE_NAME))
+        .map_err(|e| format!("Failed to resolve app config dir: {}", e))
+}
+
+/// Loads `config.toml`, falling back to defaults if it doesn't exist yet or
+/// fails to parse.
+pub fn load(app: &AppHandle) -> AppConfig {
+    let path = match config_path(app) {
+        Ok(path) => path,
+        Err(e) => {
+            log::warn!("{}", e);
+            return AppConfig::default();
+        }
+    };
+
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => toml::from_str(&contents).unwrap_or_else(|e| {
+            log::warn!("Failed to parse {}: {}. Using defaults.", path.display(), e);
+            AppConfig::default()
+        }),
+        Err(_) => AppConfig::default(),
+    }
+}
+
+/// Writes `config` back to `config.toml`, creating the app config dir if needed.
+pub fn save(app: &AppHandle, config: &AppConfig) -> Result<(), String> {
+    let path = config_path(app)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create config dir: {}", e))?;
+    }
+
+    let contents = toml::to_string_pretty(config).map_err(|e| format!("Failed to serialize config: {}", e))?;
+    std::fs::write(&path, contents).map_err(|e| format!("Failed to write config: {}", e))
+}
+
+/// Builds the shared `reqwest::Client` for a given config: applies the
+/// configured request timeout and TLS verification setting.
+pub fn build_client(config: &AppConfig) -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(config.request_timeout_secs))
+        .danger_accept_invalid_certs(!config.verify_tls)
+        .gzip(true)
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))
+}
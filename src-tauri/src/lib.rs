@@ -1,18 +1,26 @@
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use rand::Rng;
+use secrecy::ExposeSecret;
 use serde::{Deserialize, Serialize};
+use std::io::Write as _;
+use tauri::{Manager, State};
+
+mod config;
+mod kdf;
+mod oauth;
+mod token_vault;
+
+use config::{AppConfig, ConfigState};
 
 // Request structure matching backend's LoginRequest
 #[derive(Debug, Serialize, Deserialize)]
 struct LoginRequest {
     email_or_username: String,
     password: String,
-    #[serde(default = "default_client_type")]
     client_type: String,
 }
 
-fn default_client_type() -> String {
-    "desktop".to_string()
-}
-
 // Response structure matching backend's LoginResponse
 #[derive(Debug, Serialize, Deserialize)]
 struct LoginResponse {
@@ -43,7 +51,7 @@ struct TokensResponse {
 
 // Simplified response for frontend
 #[derive(Debug, Serialize, Deserialize)]
-struct AuthResponse {
+pub(crate) struct AuthResponse {
     success: bool,
     token: Option<String>,
     refresh_token: Option<String>,
@@ -60,25 +68,212 @@ struct AuthResponse {
     department: Option<String>,
 }
 
+// Body sent to /auth/prelogin
+#[derive(Debug, Serialize, Deserialize)]
+struct PreloginRequest {
+    email: String,
+}
+
+// Body sent to /auth/refresh
+#[derive(Debug, Serialize, Deserialize)]
+struct RefreshRequest {
+    grant_type: String,
+    refresh_token: String,
+}
+
+// Result of an authenticated request, including a refreshed token pair when
+// the access token had to be renewed mid-flight.
+#[derive(Debug, Serialize, Deserialize)]
+struct AuthenticatedResponse {
+    body: String,
+    new_token: Option<String>,
+    new_refresh_token: Option<String>,
+}
+
+// Extracts the fields we care about from a raw TokensResponse-shaped JSON
+// value. Shared by authenticate_user and refresh_token since the backend
+// returns the same shape from /auth/login and /auth/refresh.
+pub(crate) fn parse_auth_response(json_value: &serde_json::Value) -> Result<AuthResponse, String> {
+    let access_token = json_value.get("access_token")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+
+    let refresh_token = json_value.get("refresh_token")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+
+    let username = json_value.get("username")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+
+    let email = json_value.get("email")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+
+    let message = json_value.get("message")
+        .and_then(|v| v.as_str())
+        .unwrap_or("Login successful")
+        .to_string();
+
+    let profile_pic_url = json_value.get("profile_pic_url")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+
+    let logo_url = json_value.get("logo_url")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+
+    let organization_name = json_value.get("organization_name")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+
+    let user_role = json_value.get("user_role").cloned();
+
+    let organization_type = json_value.get("organization_type")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+
+    let tax_identification_number = json_value.get("tax_identification_number")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+
+    let staff_role = json_value.get("staff_role")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+
+    let department = json_value.get("department")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+
+    if access_token.is_empty() {
+        return Err("No access token in response".to_string());
+    }
+
+    Ok(AuthResponse {
+        success: true,
+        token: Some(access_token),
+        refresh_token: Some(refresh_token),
+        message,
+        username,
+        email,
+        profile_pic_url,
+        logo_url,
+        organization_name,
+        user_role,
+        organization_type,
+        tax_identification_number,
+        staff_role,
+        department,
+    })
+}
+
+fn current_config(state: &State<'_, ConfigState>) -> Result<AppConfig, String> {
+    state.0.lock().map_err(|e| format!("Failed to read config: {}", e)).map(|guard| guard.clone())
+}
+
+// Retry policy for transient network failures: connection errors and
+// 502/503/504 get a bounded number of attempts with exponential backoff and
+// jitter. 4xx (and other 5xx) responses are returned as-is on the first try.
+pub(crate) const MAX_ATTEMPTS: u32 = 3;
+const BASE_BACKOFF_MS: u64 = 250;
+
+fn is_retryable_status(status: reqwest::StatusCode) -> bool {
+    matches!(status.as_u16(), 502 | 503 | 504)
+}
+
+/// GET/HEAD/PUT/DELETE are safe to blindly retry. POST/PATCH (and anything
+/// else) may not be idempotent on the backend, so a lost response could mean
+/// the mutation already landed — retrying risks applying it twice.
+pub(crate) fn is_retryable_method(method: &str) -> bool {
+    matches!(method.to_uppercase().as_str(), "GET" | "HEAD" | "PUT" | "DELETE")
+}
+
+/// Attempt budget for the generic API gateway commands: the full retry
+/// budget for idempotent methods, a single try otherwise.
+fn max_attempts_for(method: &str) -> u32 {
+    if is_retryable_method(method) {
+        MAX_ATTEMPTS
+    } else {
+        1
+    }
+}
+
+async fn backoff_sleep(attempt: u32) {
+    let base_ms = BASE_BACKOFF_MS * 2u64.pow(attempt);
+    let jitter_ms = rand::thread_rng().gen_range(0..=base_ms / 4);
+    tokio::time::sleep(std::time::Duration::from_millis(base_ms + jitter_ms)).await;
+}
+
+/// Sends a request built fresh by `build` on each attempt, retrying on
+/// connection errors or 502/503/504 with exponential backoff and jitter
+/// (~250ms, ~500ms, ~1s) up to `max_attempts` times. Never retries on 4xx.
+pub(crate) async fn send_with_retry<F>(max_attempts: u32, build: F) -> Result<reqwest::Response, String>
+where
+    F: Fn() -> Result<reqwest::RequestBuilder, String>,
+{
+    let mut attempt = 0;
+    loop {
+        let is_last_attempt = attempt + 1 >= max_attempts;
+
+        match build()?.send().await {
+            Ok(response) => {
+                if is_last_attempt || !is_retryable_status(response.status()) {
+                    return Ok(response);
+                }
+            }
+            Err(e) => {
+                if is_last_attempt || !e.is_connect() {
+                    return Err(format!("Connection failed: {}", e));
+                }
+            }
+        }
+
+        backoff_sleep(attempt).await;
+        attempt += 1;
+    }
+}
+
 // Tauri command to authenticate user with Kastaem backend
 #[tauri::command]
-async fn authenticate_user(email: String, password: String) -> Result<AuthResponse, String> {
-    let backend_url = "http://127.0.0.1:8000/auth/login";
+async fn authenticate_user(
+    email: String,
+    password: String,
+    http_client: State<'_, reqwest::Client>,
+    config_state: State<'_, ConfigState>,
+) -> Result<AuthResponse, String> {
+    let config = current_config(&config_state)?;
+    let backend_url = format!("{}/auth/login", config.base_url);
+    let client = http_client.inner();
+
+    // Stretch the password client-side before it ever leaves the device, so
+    // the backend only ever sees a derived hash. Backends that don't support
+    // prelogin yet (404) fall back to sending the plaintext password.
+    let password_to_send = match prelogin(client, &config, &email).await? {
+        Some(prelogin_response) => kdf::derive_master_hash(
+            &email,
+            &password,
+            prelogin_response.kdf,
+            prelogin_response.kdf_iterations,
+        )?,
+        None => password,
+    };
 
-    let client = reqwest::Client::new();
     let auth_data = LoginRequest {
         email_or_username: email,
-        password,
-        client_type: "desktop".to_string(),
+        password: password_to_send,
+        client_type: config.client_type.clone(),
     };
 
-    match client
-        .post(backend_url)
-        .header("X-Client-Type", "desktop")
-        .header("Content-Type", "application/json")
-        .json(&auth_data)
-        .send()
-        .await
+    match send_with_retry(MAX_ATTEMPTS, || {
+        Ok(client
+            .post(&backend_url)
+            .header("X-Client-Type", config.client_type.as_str())
+            .header("Content-Type", "application/json")
+            .json(&auth_data))
+    })
+    .await
     {
         Ok(response) => {
             if response.status().is_success() {
@@ -88,8 +283,7 @@ async fn authenticate_user(email: String, password: String) -> Result<AuthRespon
                     Err(e) => return Err(format!("Failed to read response: {}", e)),
                 };
 
-                // Log the response for debugging
-                println!("Backend response: {}", response_text);
+                log::debug!("Received auth response ({} bytes)", response_text.len());
 
                 // Try to parse as JSON Value first
                 let json_value: serde_json::Value = match serde_json::from_str(&response_text) {
@@ -98,81 +292,20 @@ async fn authenticate_user(email: String, password: String) -> Result<AuthRespon
                 };
 
                 // The backend returns TokensResponse directly for desktop/mobile
-                // Extract fields directly from the root object
-                let access_token = json_value.get("access_token")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("")
-                    .to_string();
-
-                let refresh_token = json_value.get("refresh_token")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("")
-                    .to_string();
-
-                let username = json_value.get("username")
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string());
-
-                let email = json_value.get("email")
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string());
-
-                let message = json_value.get("message")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("Login successful")
-                    .to_string();
-
-                let profile_pic_url = json_value.get("profile_pic_url")
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string());
-
-                let logo_url = json_value.get("logo_url")
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string());
-
-                let organization_name = json_value.get("organization_name")
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string());
-
-                let user_role = json_value.get("user_role").cloned();
-
-                let organization_type = json_value.get("organization_type")
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string());
-
-                let tax_identification_number = json_value.get("tax_identification_number")
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string());
-
-                let staff_role = json_value.get("staff_role")
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string());
-
-                let department = json_value.get("department")
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string());
-
-                // Check if we got valid tokens
-                if access_token.is_empty() {
-                    return Err("No access token in response".to_string());
+                let auth_response = parse_auth_response(&json_value)?;
+
+                // Best-effort: persist the new session to the OS credential
+                // store so subsequent requests don't need the frontend to
+                // hold the raw tokens. A vault failure shouldn't fail login.
+                if let (Some(username), Some(access_token), Some(refresh_token)) =
+                    (&auth_response.username, &auth_response.token, &auth_response.refresh_token)
+                {
+                    if let Err(e) = token_vault::store(username, access_token, refresh_token) {
+                        log::warn!("Failed to persist tokens to OS credential store: {}", e);
+                    }
                 }
 
-                Ok(AuthResponse {
-                    success: true,
-                    token: Some(access_token),
-                    refresh_token: Some(refresh_token),
-                    message,
-                    username,
-                    email,
-                    profile_pic_url,
-                    logo_url,
-                    organization_name,
-                    user_role,
-                    organization_type,
-                    tax_identification_number,
-                    staff_role,
-                    department,
-                })
+                Ok(auth_response)
             } else {
                 let status = response.status();
                 let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
@@ -208,83 +341,421 @@ async fn authenticate_user(email: String, password: String) -> Result<AuthRespon
                 Err(generic_message.to_string())
             }
         }
-        Err(e) => Err(format!("Connection failed: {}. Make sure backend is running on http://127.0.0.1:8000", e)),
+        Err(e) => Err(format!("{}. Make sure backend is running at {}", e, config.base_url)),
     }
 }
 
-// Tauri command for making authenticated API calls
+// Fetches the KDF parameters for `email` from /auth/prelogin. Returns `None`
+// when the backend doesn't expose the endpoint yet, so callers can fall back
+// to plaintext rather than hard-failing login.
+async fn prelogin(client: &reqwest::Client, config: &AppConfig, email: &str) -> Result<Option<kdf::PreloginResponse>, String> {
+    let backend_url = format!("{}/auth/prelogin", config.base_url);
+
+    let response = send_with_retry(MAX_ATTEMPTS, || {
+        Ok(client
+            .post(&backend_url)
+            .header("X-Client-Type", config.client_type.as_str())
+            .header("Content-Type", "application/json")
+            .json(&PreloginRequest { email: email.to_string() }))
+    })
+    .await?;
+
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+
+    if !response.status().is_success() {
+        return Err("Failed to start login".to_string());
+    }
+
+    response
+        .json::<kdf::PreloginResponse>()
+        .await
+        .map(Some)
+        .map_err(|e| format!("Failed to parse prelogin response: {}", e))
+}
+
+// Exchanges a refresh token for a new access/refresh token pair. Shared by
+// the `refresh_token` command and the transparent retry in
+// `authenticated_request`.
+async fn do_refresh(client: &reqwest::Client, config: &AppConfig, refresh_token: String) -> Result<AuthResponse, String> {
+    let backend_url = format!("{}/auth/refresh", config.base_url);
+    let refresh_data = RefreshRequest {
+        grant_type: "refresh_token".to_string(),
+        refresh_token,
+    };
+
+    let response = send_with_retry(MAX_ATTEMPTS, || {
+        Ok(client
+            .post(&backend_url)
+            .header("X-Client-Type", config.client_type.as_str())
+            .header("Content-Type", "application/json")
+            .json(&refresh_data))
+    })
+    .await?;
+
+    if response.status().is_success() {
+        let response_text = response.text().await
+            .map_err(|e| format!("Failed to read response: {}", e))?;
+
+        let json_value: serde_json::Value = serde_json::from_str(&response_text)
+            .map_err(|e| format!("Failed to parse JSON: {}. Response was: {}", e, response_text))?;
+
+        parse_auth_response(&json_value)
+    } else {
+        Err("Session expired. Please log in again.".to_string())
+    }
+}
+
+// Tauri command to exchange a refresh token for a new access/refresh token pair
 #[tauri::command]
-async fn authenticated_request(
-    url: String,
-    method: String,
-    token: String,
-    body: Option<String>,
-) -> Result<String, String> {
-    let client = reqwest::Client::new();
+async fn refresh_token(
+    refresh_token: String,
+    http_client: State<'_, reqwest::Client>,
+    config_state: State<'_, ConfigState>,
+) -> Result<AuthResponse, String> {
+    let config = current_config(&config_state)?;
+    do_refresh(http_client.inner(), &config, refresh_token).await
+}
+
+// Bodies larger than this are worth the CPU cost of gzip-compressing before
+// they go over the wire.
+const COMPRESSION_THRESHOLD_BYTES: usize = 1024;
+
+fn gzip_compress(body: &str) -> Result<Vec<u8>, String> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(body.as_bytes())
+        .and_then(|_| encoder.finish())
+        .map_err(|e| format!("Failed to compress request body: {}", e))
+}
 
+fn build_request(
+    client: &reqwest::Client,
+    config: &AppConfig,
+    method: &str,
+    url: &str,
+    token: &str,
+    body: &Option<String>,
+    compress: bool,
+) -> Result<reqwest::RequestBuilder, String> {
     let mut request = match method.to_uppercase().as_str() {
-        "GET" => client.get(&url),
-        "POST" => client.post(&url),
-        "PUT" => client.put(&url),
-        "DELETE" => client.delete(&url),
-        "PATCH" => client.patch(&url),
+        "GET" => client.get(url),
+        "POST" => client.post(url),
+        "PUT" => client.put(url),
+        "DELETE" => client.delete(url),
+        "PATCH" => client.patch(url),
         _ => return Err(format!("Unsupported HTTP method: {}", method)),
     };
 
-    // Add required headers for desktop client
     request = request
         .header("Authorization", format!("Bearer {}", token))
-        .header("X-Client-Type", "desktop")
-        .header("Content-Type", "application/json");
+        .header("X-Client-Type", config.client_type.as_str())
+        .header("Content-Type", "application/json")
+        .header("Accept-Encoding", "gzip");
 
-    // Add body if provided
     if let Some(body_str) = body {
-        request = request.body(body_str);
+        if compress && body_str.len() > COMPRESSION_THRESHOLD_BYTES {
+            request = request
+                .header("Content-Encoding", "gzip")
+                .body(gzip_compress(body_str)?);
+        } else {
+            request = request.body(body_str.clone());
+        }
     }
 
-    match request.send().await {
-        Ok(response) => {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_else(|_| "Failed to read response".to_string());
+    Ok(request)
+}
 
-            if status.is_success() {
-                Ok(text)
-            } else {
-                Err(format!("Request failed ({}): {}", status, text))
-            }
-        }
-        Err(e) => Err(format!("Connection failed: {}", e)),
+// Tauri command for making authenticated API calls
+#[tauri::command]
+async fn authenticated_request(
+    url: String,
+    method: String,
+    token: String,
+    refresh_token: String,
+    body: Option<String>,
+    compress: Option<bool>,
+    http_client: State<'_, reqwest::Client>,
+    config_state: State<'_, ConfigState>,
+) -> Result<AuthenticatedResponse, String> {
+    let config = current_config(&config_state)?;
+    let client = http_client.inner();
+    let compress = compress.unwrap_or(false);
+    let max_attempts = max_attempts_for(&method);
+
+    let response = send_with_retry(max_attempts, || build_request(client, &config, &method, &url, &token, &body, compress)).await?;
+
+    if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+        // Access token expired mid-flight: refresh once and retry the
+        // original request before giving up.
+        let refreshed = do_refresh(client, &config, refresh_token).await?;
+        let new_token = refreshed.token
+            .ok_or_else(|| "Refresh succeeded but returned no access token".to_string())?;
+
+        let retry_response = send_with_retry(max_attempts, || build_request(client, &config, &method, &url, &new_token, &body, compress)).await?;
+
+        let status = retry_response.status();
+        let text = retry_response.text().await.unwrap_or_else(|_| "Failed to read response".to_string());
+
+        return if status.is_success() {
+            Ok(AuthenticatedResponse {
+                body: text,
+                new_token: Some(new_token),
+                new_refresh_token: refreshed.refresh_token,
+            })
+        } else {
+            Err(format!("Request failed ({}): {}", status, text))
+        };
+    }
+
+    let status = response.status();
+    let text = response.text().await.unwrap_or_else(|_| "Failed to read response".to_string());
+
+    if status.is_success() {
+        Ok(AuthenticatedResponse {
+            body: text,
+            new_token: None,
+            new_refresh_token: None,
+        })
+    } else {
+        Err(format!("Request failed ({}): {}", status, text))
+    }
+}
+
+async fn do_logout(client: &reqwest::Client, config: &AppConfig, token: String) -> Result<String, String> {
+    let backend_url = format!("{}/auth/logout", config.base_url);
+
+    let response = send_with_retry(MAX_ATTEMPTS, || {
+        Ok(client
+            .post(&backend_url)
+            .header("Authorization", format!("Bearer {}", token))
+            .header("X-Client-Type", config.client_type.as_str()))
+    })
+    .await?;
+
+    if response.status().is_success() {
+        Ok("Logged out successfully".to_string())
+    } else {
+        Err("Logout failed".to_string())
     }
 }
 
 // Tauri command for logout
 #[tauri::command]
-async fn logout_user(token: String) -> Result<String, String> {
-    let backend_url = "http://127.0.0.1:8000/auth/logout";
+async fn logout_user(
+    token: String,
+    http_client: State<'_, reqwest::Client>,
+    config_state: State<'_, ConfigState>,
+) -> Result<String, String> {
+    let config = current_config(&config_state)?;
+    do_logout(http_client.inner(), &config, token).await
+}
 
-    let client = reqwest::Client::new();
+// Tauri command to persist a token pair to the OS credential store directly,
+// e.g. after a frontend-driven flow that doesn't go through authenticate_user
+#[tauri::command]
+async fn store_tokens(account: String, access_token: String, refresh_token: String) -> Result<(), String> {
+    token_vault::store(&account, &access_token, &refresh_token)
+}
 
-    match client
-        .post(backend_url)
-        .header("Authorization", format!("Bearer {}", token))
-        .header("X-Client-Type", "desktop")
-        .send()
-        .await
+// Tauri command to read back the account that last logged in successfully
+#[tauri::command]
+async fn get_stored_username() -> Result<Option<String>, String> {
+    token_vault::stored_username()
+}
+
+// Tauri command to remove a stored token pair, e.g. on explicit sign-out
+#[tauri::command]
+async fn clear_tokens(account: String) -> Result<(), String> {
+    token_vault::clear(&account)
+}
+
+// Vault-backed variant of `authenticated_request`: the token is read from the
+// OS credential store by account key instead of being passed in from the
+// frontend, and a transparent refresh updates the vault in place rather than
+// handing the new tokens back across the webview boundary.
+#[tauri::command]
+async fn authenticated_request_stored(
+    account: String,
+    url: String,
+    method: String,
+    body: Option<String>,
+    compress: Option<bool>,
+    http_client: State<'_, reqwest::Client>,
+    config_state: State<'_, ConfigState>,
+) -> Result<String, String> {
+    let config = current_config(&config_state)?;
+    let tokens = token_vault::load(&account)?;
+    let client = http_client.inner();
+    let compress = compress.unwrap_or(false);
+    let max_attempts = max_attempts_for(&method);
+
+    let response = send_with_retry(max_attempts, || {
+        build_request(client, &config, &method, &url, tokens.access_token.expose_secret(), &body, compress)
+    })
+    .await?;
+
+    if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+        let refreshed = do_refresh(client, &config, tokens.refresh_token.expose_secret().clone()).await?;
+        let new_token = refreshed.token
+            .ok_or_else(|| "Refresh succeeded but returned no access token".to_string())?;
+
+        if let Some(new_refresh_token) = &refreshed.refresh_token {
+            token_vault::store(&account, &new_token, new_refresh_token)?;
+        }
+
+        let retry_response = send_with_retry(max_attempts, || build_request(client, &config, &method, &url, &new_token, &body, compress)).await?;
+
+        let status = retry_response.status();
+        let text = retry_response.text().await.unwrap_or_else(|_| "Failed to read response".to_string());
+
+        return if status.is_success() {
+            Ok(text)
+        } else {
+            Err(format!("Request failed ({}): {}", status, text))
+        };
+    }
+
+    let status = response.status();
+    let text = response.text().await.unwrap_or_else(|_| "Failed to read response".to_string());
+
+    if status.is_success() {
+        Ok(text)
+    } else {
+        Err(format!("Request failed ({}): {}", status, text))
+    }
+}
+
+// Vault-backed variant of `logout_user`: reads the access token from the OS
+// credential store and clears the stored session afterwards regardless of
+// whether the backend call succeeded.
+#[tauri::command]
+async fn logout_user_stored(
+    account: String,
+    http_client: State<'_, reqwest::Client>,
+    config_state: State<'_, ConfigState>,
+) -> Result<String, String> {
+    let config = current_config(&config_state)?;
+    let tokens = token_vault::load(&account)?;
+    let result = do_logout(http_client.inner(), &config, tokens.access_token.expose_secret().clone()).await;
+    token_vault::clear(&account)?;
+    result
+}
+
+// Tauri command to authenticate via the browser-based OAuth2
+// authorization-code flow, as an alternative to password login
+#[tauri::command]
+async fn begin_oauth(
+    app: tauri::AppHandle,
+    client_id: String,
+    redirect_uri: String,
+    http_client: State<'_, reqwest::Client>,
+    config_state: State<'_, ConfigState>,
+) -> Result<AuthResponse, String> {
+    let config = current_config(&config_state)?;
+    let auth_response = oauth::begin_oauth(app, http_client.inner(), &config, client_id, redirect_uri).await?;
+
+    // Best-effort, same as the password-login path: persist the new session
+    // to the OS credential store so the frontend doesn't need to hold onto
+    // the raw tokens.
+    if let (Some(username), Some(access_token), Some(refresh_token)) =
+        (&auth_response.username, &auth_response.token, &auth_response.refresh_token)
     {
-        Ok(response) => {
-            if response.status().is_success() {
-                Ok("Logged out successfully".to_string())
-            } else {
-                Err("Logout failed".to_string())
-            }
+        if let Err(e) = token_vault::store(username, access_token, refresh_token) {
+            log::warn!("Failed to persist tokens to OS credential store: {}", e);
         }
-        Err(e) => Err(format!("Request failed: {}", e)),
+    }
+
+    Ok(auth_response)
+}
+
+// Tauri command to read the current runtime config (base URL, client type, etc.)
+#[tauri::command]
+async fn get_config(config_state: State<'_, ConfigState>) -> Result<AppConfig, String> {
+    current_config(&config_state)
+}
+
+// Tauri command to switch the backend environment (e.g. staging vs.
+// production) without rebuilding the app. Persists to config.toml.
+#[tauri::command]
+async fn set_base_url(
+    app: tauri::AppHandle,
+    base_url: String,
+    config_state: State<'_, ConfigState>,
+) -> Result<AppConfig, String> {
+    let updated = {
+        let mut config = config_state.0.lock().map_err(|e| format!("Failed to update config: {}", e))?;
+        config.base_url = base_url;
+        config.clone()
+    };
+
+    config::save(&app, &updated)?;
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_retryable_method_allows_idempotent_verbs() {
+        assert!(is_retryable_method("GET"));
+        assert!(is_retryable_method("get"));
+        assert!(is_retryable_method("HEAD"));
+        assert!(is_retryable_method("PUT"));
+        assert!(is_retryable_method("DELETE"));
+    }
+
+    #[test]
+    fn is_retryable_method_rejects_non_idempotent_verbs() {
+        assert!(!is_retryable_method("POST"));
+        assert!(!is_retryable_method("post"));
+        assert!(!is_retryable_method("PATCH"));
+    }
+
+    #[test]
+    fn is_retryable_status_matches_only_known_5xx() {
+        assert!(is_retryable_status(reqwest::StatusCode::BAD_GATEWAY));
+        assert!(is_retryable_status(reqwest::StatusCode::SERVICE_UNAVAILABLE));
+        assert!(is_retryable_status(reqwest::StatusCode::GATEWAY_TIMEOUT));
+        assert!(!is_retryable_status(reqwest::StatusCode::INTERNAL_SERVER_ERROR));
+        assert!(!is_retryable_status(reqwest::StatusCode::NOT_FOUND));
+    }
+
+    #[test]
+    fn max_attempts_for_gates_on_method() {
+        assert_eq!(max_attempts_for("GET"), MAX_ATTEMPTS);
+        assert_eq!(max_attempts_for("POST"), 1);
+        assert_eq!(max_attempts_for("PATCH"), 1);
+    }
+
+    #[test]
+    fn compression_threshold_is_a_strict_boundary() {
+        let client = reqwest::Client::new();
+        let config = AppConfig::default();
+        let content_encoding = |body: String| {
+            build_request(&client, &config, "POST", "http://127.0.0.1:8000/items", "token", &Some(body), true)
+                .unwrap()
+                .build()
+                .unwrap()
+                .headers()
+                .get("Content-Encoding")
+                .map(|v| v.to_str().unwrap().to_string())
+        };
+
+        let at_threshold = "a".repeat(COMPRESSION_THRESHOLD_BYTES);
+        let over_threshold = "a".repeat(COMPRESSION_THRESHOLD_BYTES + 1);
+
+        assert_eq!(content_encoding(at_threshold), None);
+        assert_eq!(content_encoding(over_threshold), Some("gzip".to_string()));
     }
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
   tauri::Builder::default()
+    .plugin(tauri_plugin_opener::init())
     .setup(|app| {
       if cfg!(debug_assertions) {
         app.handle().plugin(
@@ -293,12 +764,27 @@ pub fn run() {
             .build(),
         )?;
       }
+
+      let app_config = config::load(&app.handle().clone());
+      let http_client = config::build_client(&app_config)?;
+      app.manage(http_client);
+      app.manage(ConfigState(std::sync::Mutex::new(app_config)));
+
       Ok(())
     })
     .invoke_handler(tauri::generate_handler![
       authenticate_user,
       authenticated_request,
-      logout_user
+      refresh_token,
+      logout_user,
+      store_tokens,
+      get_stored_username,
+      clear_tokens,
+      authenticated_request_stored,
+      logout_user_stored,
+      begin_oauth,
+      get_config,
+      set_base_url
     ])
     .run(tauri::generate_context!())
     .expect("error while running tauri application");
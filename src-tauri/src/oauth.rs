@@ -0,0 +1,213 @@
+// Browser-based OAuth2 authorization-code flow, for SSO providers that won't
+// accept the raw-password flow in `authenticate_user`.
+//
+// We open the backend's authorize endpoint in the system browser and spin up
+// a short-lived localhost listener on the redirect port to catch the
+// `?code=&state=` callback. The `state` value is generated per attempt and
+// checked against what comes back to guard against CSRF.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::time::{Duration, Instant};
+
+/// How long we'll wait for the browser to complete the redirect before
+/// giving up and releasing the callback port.
+const OAUTH_CALLBACK_TIMEOUT: Duration = Duration::from_secs(120);
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tauri_plugin_opener::OpenerExt;
+
+use crate::config::AppConfig;
+use crate::{parse_auth_response, AuthResponse};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TokenRequest {
+    grant_type: String,
+    code: String,
+    client_id: String,
+    redirect_uri: String,
+}
+
+fn random_state() -> String {
+    let mut rng = rand::thread_rng();
+    (0..32).map(|_| format!("{:x}", rng.gen_range(0..16u8))).collect()
+}
+
+/// Pulls the port a redirect URI listens on, e.g. `http://127.0.0.1:4200/callback` -> 4200.
+fn redirect_port(redirect_uri: &str) -> Result<u16, String> {
+    let without_scheme = redirect_uri
+        .split_once("://")
+        .map(|(_, rest)| rest)
+        .unwrap_or(redirect_uri);
+    let host_and_port = without_scheme.split('/').next().unwrap_or(without_scheme);
+    let port_str = host_and_port
+        .split_once(':')
+        .map(|(_, port)| port)
+        .ok_or_else(|| format!("redirect_uri must include an explicit port: {}", redirect_uri))?;
+
+    port_str
+        .parse()
+        .map_err(|_| format!("Invalid port in redirect_uri: {}", redirect_uri))
+}
+
+/// Blocks on a single HTTP request to the local redirect listener and pulls
+/// `code`/`state` out of the request line's query string. Responds with a
+/// minimal page so the browser tab doesn't hang on a spinner.
+fn wait_for_redirect(listener: TcpListener) -> Result<(String, String), String> {
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| format!("Failed to configure OAuth callback listener: {}", e))?;
+
+    let deadline = Instant::now() + OAUTH_CALLBACK_TIMEOUT;
+    let mut stream = loop {
+        match listener.accept() {
+            Ok((stream, _)) => break stream,
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                if Instant::now() >= deadline {
+                    return Err("Timed out waiting for the OAuth redirect. Please try logging in again.".to_string());
+                }
+                std::thread::sleep(Duration::from_millis(100));
+            }
+            Err(e) => return Err(format!("Failed to accept OAuth redirect: {}", e)),
+        }
+    };
+
+    stream
+        .set_nonblocking(false)
+        .map_err(|e| format!("Failed to configure OAuth callback connection: {}", e))?;
+    stream
+        .set_read_timeout(Some(Duration::from_secs(10)))
+        .map_err(|e| format!("Failed to configure OAuth callback connection: {}", e))?;
+
+    let mut reader = BufReader::new(stream.try_clone().map_err(|e| e.to_string())?);
+    let mut request_line = String::new();
+    reader
+        .read_line(&mut request_line)
+        .map_err(|e| format!("Failed to read OAuth redirect: {}", e))?;
+
+    let path = request_line
+        .split_whitespace()
+        .nth(1)
+        .ok_or_else(|| "Malformed OAuth redirect request".to_string())?;
+
+    let query = path.split_once('?').map(|(_, q)| q).unwrap_or("");
+
+    let mut code = None;
+    let mut state = None;
+    for pair in query.split('&') {
+        if let Some((key, value)) = pair.split_once('=') {
+            match key {
+                "code" => code = Some(urlencoding::decode(value).unwrap_or_default().into_owned()),
+                "state" => state = Some(urlencoding::decode(value).unwrap_or_default().into_owned()),
+                _ => {}
+            }
+        }
+    }
+
+    let body = "<html><body>Login complete. You can close this tab and return to the app.</body></html>";
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\n\r\n{}",
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes());
+
+    let code = code.ok_or_else(|| "OAuth redirect missing code".to_string())?;
+    let state = state.ok_or_else(|| "OAuth redirect missing state".to_string())?;
+
+    Ok((code, state))
+}
+
+async fn exchange_code(
+    client: &reqwest::Client,
+    config: &AppConfig,
+    client_id: &str,
+    redirect_uri: &str,
+    code: &str,
+) -> Result<AuthResponse, String> {
+    let backend_url = format!("{}/oauth/token", config.base_url);
+
+    let token_data = TokenRequest {
+        grant_type: "authorization_code".to_string(),
+        code: code.to_string(),
+        client_id: client_id.to_string(),
+        redirect_uri: redirect_uri.to_string(),
+    };
+
+    let response = crate::send_with_retry(crate::MAX_ATTEMPTS, || {
+        Ok(client
+            .post(&backend_url)
+            .header("X-Client-Type", config.client_type.as_str())
+            .header("Content-Type", "application/json")
+            .json(&token_data))
+    })
+    .await?;
+
+    if !response.status().is_success() {
+        return Err("Failed to exchange authorization code for tokens".to_string());
+    }
+
+    let response_text = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read response: {}", e))?;
+
+    let json_value: serde_json::Value = serde_json::from_str(&response_text)
+        .map_err(|e| format!("Failed to parse JSON: {}. Response was: {}", e, response_text))?;
+
+    parse_auth_response(&json_value)
+}
+
+/// Drives the full authorization-code flow: opens the authorize URL, waits
+/// for the redirect, verifies `state`, then exchanges the code for tokens.
+pub async fn begin_oauth(
+    app: tauri::AppHandle,
+    client: &reqwest::Client,
+    config: &AppConfig,
+    client_id: String,
+    redirect_uri: String,
+) -> Result<AuthResponse, String> {
+    let state = random_state();
+    let authorize_url = format!(
+        "{}/oauth/authorize?response_type=code&client_id={}&state={}&redirect_uri={}",
+        config.base_url,
+        urlencoding::encode(&client_id),
+        urlencoding::encode(&state),
+        urlencoding::encode(&redirect_uri),
+    );
+
+    let port = redirect_port(&redirect_uri)?;
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .map_err(|e| format!("Failed to start OAuth callback listener on port {}: {}", port, e))?;
+
+    app.opener()
+        .open_url(authorize_url, None::<&str>)
+        .map_err(|e| format!("Failed to open browser for OAuth login: {}", e))?;
+
+    let (code, returned_state) = tokio::task::spawn_blocking(move || wait_for_redirect(listener))
+        .await
+        .map_err(|e| format!("OAuth callback task panicked: {}", e))??;
+
+    if returned_state != state {
+        return Err("OAuth state mismatch; possible CSRF attempt. Aborting login.".to_string());
+    }
+
+    exchange_code(client, config, &client_id, &redirect_uri, &code).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redirect_port_parses_host_and_path() {
+        assert_eq!(redirect_port("http://127.0.0.1:4200/callback").unwrap(), 4200);
+        assert_eq!(redirect_port("http://localhost:8080").unwrap(), 8080);
+    }
+
+    #[test]
+    fn redirect_port_requires_explicit_port() {
+        assert!(redirect_port("http://127.0.0.1/callback").is_err());
+    }
+}
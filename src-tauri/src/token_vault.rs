@@ -0,0 +1,91 @@
+// OS-keychain-backed storage for access/refresh tokens.
+//
+// Tokens are written to the platform credential store (Keychain on macOS,
+// Credential Manager on Windows, Secret Service on Linux) via `keyring`
+// instead of round-tripping through the webview as plain strings. Values are
+// wrapped in `secrecy::SecretString` on the way out so they are zeroized on
+// drop and never land in a log line by accident.
+
+use keyring::Entry;
+use secrecy::SecretString;
+
+const SERVICE: &str = "com.kastaem.desktop";
+const USERNAME_KEY: &str = "current_username";
+
+pub struct TokenPair {
+    pub access_token: SecretString,
+    pub refresh_token: SecretString,
+}
+
+fn access_key(account: &str) -> String {
+    format!("{account}:access_token")
+}
+
+fn refresh_key(account: &str) -> String {
+    format!("{account}:refresh_token")
+}
+
+fn entry(key: &str) -> Result<Entry, String> {
+    Entry::new(SERVICE, key).map_err(|e| format!("Failed to open credential store: {}", e))
+}
+
+/// Persists an access/refresh token pair for `account` and records it as the
+/// current signed-in user.
+pub fn store(account: &str, access_token: &str, refresh_token: &str) -> Result<(), String> {
+    entry(&access_key(account))?
+        .set_password(access_token)
+        .map_err(|e| format!("Failed to store access token: {}", e))?;
+
+    entry(&refresh_key(account))?
+        .set_password(refresh_token)
+        .map_err(|e| format!("Failed to store refresh token: {}", e))?;
+
+    entry(USERNAME_KEY)?
+        .set_password(account)
+        .map_err(|e| format!("Failed to record current account: {}", e))?;
+
+    Ok(())
+}
+
+/// Loads the stored token pair for `account`.
+pub fn load(account: &str) -> Result<TokenPair, String> {
+    let access_token = entry(&access_key(account))?
+        .get_password()
+        .map_err(|_| "No stored session for this account. Please log in again.".to_string())?;
+
+    let refresh_token = entry(&refresh_key(account))?
+        .get_password()
+        .map_err(|_| "No stored session for this account. Please log in again.".to_string())?;
+
+    Ok(TokenPair {
+        access_token: SecretString::from(access_token),
+        refresh_token: SecretString::from(refresh_token),
+    })
+}
+
+/// Returns the account that last completed a successful login, if any.
+pub fn stored_username() -> Result<Option<String>, String> {
+    match entry(USERNAME_KEY)?.get_password() {
+        Ok(username) => Ok(Some(username)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Failed to read stored account: {}", e)),
+    }
+}
+
+/// Removes the stored token pair for `account`, plus the current-account
+/// marker if it points at `account`.
+pub fn clear(account: &str) -> Result<(), String> {
+    if let Ok(entry) = entry(&access_key(account)) {
+        let _ = entry.delete_credential();
+    }
+    if let Ok(entry) = entry(&refresh_key(account)) {
+        let _ = entry.delete_credential();
+    }
+    if stored_username()?.as_deref() == Some(account) {
+        if let Ok(entry) = entry(USERNAME_KEY) {
+            let _ = entry.delete_credential();
+        }
+    }
+
+    Ok(())
+}